@@ -2,6 +2,12 @@
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        .invoke_handler(tauri::generate_handler![
+            set_traffic_light_position,
+            get_traffic_light_position,
+            start_window_drag,
+            get_titlebar_geometry
+        ])
         .setup(|app| {
             // 调试模式下启用日志插件，日志级别为 Info
             if cfg!(debug_assertions) {
@@ -17,8 +23,11 @@ pub fn run() {
 
                 // 获取主窗口实例
                 if let Some(window) = app.get_webview_window("main") {
-                    // 根据屏幕宽度设置窗口大小，宽度占屏幕的 80%
-                    apply_window_size_from_screen_width(&window, 0.8);
+                    // 优先恢复上次保存的窗口布局；首次启动或保存位置已不在任何显示器范围内
+                    // 时，退回按屏幕宽度的 80% 居中布局
+                    restore_or_apply_window_state(&app.handle().clone(), &window);
+                    // 监听移动/缩放（防抖保存）和关闭（立即保存），持久化窗口布局
+                    install_window_state_persistence(&app.handle().clone(), &window);
 
                     // macOS 平台特定配置
                     #[cfg(target_os = "macos")]
@@ -37,6 +46,9 @@ pub fn run() {
                         // 监听窗口缩放：缩放期间交通灯透明，鼠标释放后再显示并重定位
                         install_macos_traffic_lights_resize_handling(&window, 6.0, 6.0);
                     }
+
+                    // 跨平台：窗口尺寸或 DPI 变化时，通知前端重新布局自绘标题栏控件
+                    install_titlebar_geometry_change_notifications(&window);
                 }
             }
             Ok(())
@@ -46,6 +58,386 @@ pub fn run() {
         .expect("error while running tauri application");
 }
 
+/// 运行时重新设置交通灯（关闭、最小化、最大化按钮）的偏移量
+/// - 参数: window_label - 目标窗口的 label
+/// - 参数: x - X 轴偏移量（向右为正）
+/// - 参数: y - Y 轴偏移量（向下为正）
+///
+/// 供前端自绘标题栏在布局变化后动态对齐原生按钮，类似 Electron 的 `trafficLightPosition`。
+#[tauri::command]
+fn set_traffic_light_position(
+    app: tauri::AppHandle,
+    window_label: String,
+    x: f64,
+    y: f64,
+) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        use tauri::Manager;
+
+        let window = app
+            .get_webview_window(&window_label)
+            .ok_or_else(|| format!("窗口不存在: {window_label}"))?;
+        apply_macos_traffic_lights_offset(&window, y, x);
+        Ok(())
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = (app, window_label, x, y);
+        Err("set_traffic_light_position 仅支持 macOS".to_string())
+    }
+}
+
+/// 获取交通灯当前的偏移量
+/// - 参数: window_label - 目标窗口的 label
+/// - 返回: (x, y) 偏移量
+#[tauri::command]
+fn get_traffic_light_position(window_label: String) -> Result<(f64, f64), String> {
+    #[cfg(target_os = "macos")]
+    {
+        let (y_offset, x_offset) = get_macos_traffic_lights_offset_by_label(&window_label);
+        Ok((x_offset, y_offset))
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = window_label;
+        Err("get_traffic_light_position 仅支持 macOS".to_string())
+    }
+}
+
+/// 开始拖拽窗口
+///
+/// 前端在自绘标题栏里监听注册为可拖拽区域的元素的 `mousedown`，并调用此命令发起系统原生的
+/// 窗口拖拽，效果等同于原生标题栏的拖拽手柄。
+#[tauri::command]
+fn start_window_drag(window: tauri::WebviewWindow) -> Result<(), String> {
+    window.start_dragging().map_err(|error| error.to_string())
+}
+
+/// 自定义标题栏控件应当避让的区域，类比 Chromium Window Controls Overlay 的
+/// `titlebar-area-x/width/height` 几何信息，坐标原点在窗口左上角
+#[derive(Clone, Copy, Default, serde::Serialize)]
+struct TitlebarGeometry {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+/// 获取当前平台下原生控件占用的标题栏几何信息
+/// - macOS: 从窗口左上角到交通灯按钮外接矩形右下角的保留区域
+/// - Windows / Linux: 没有原生控件占位，返回零尺寸，前端可在整个标题栏自由布局
+#[tauri::command]
+fn get_titlebar_geometry(window: tauri::WebviewWindow) -> Result<TitlebarGeometry, String> {
+    Ok(compute_titlebar_geometry(&window))
+}
+
+fn compute_titlebar_geometry(window: &tauri::WebviewWindow) -> TitlebarGeometry {
+    #[cfg(target_os = "macos")]
+    {
+        macos_titlebar_geometry(window)
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = window;
+        TitlebarGeometry::default()
+    }
+}
+
+/// 跨平台：窗口尺寸或 DPI 发生变化时，向前端广播最新的标题栏几何信息
+fn install_titlebar_geometry_change_notifications(window: &tauri::WebviewWindow) {
+    use tauri::Emitter;
+
+    let window_for_event = window.clone();
+    window.on_window_event(move |event| {
+        if matches!(
+            event,
+            tauri::WindowEvent::Resized(_) | tauri::WindowEvent::ScaleFactorChanged { .. }
+        ) {
+            let geometry = compute_titlebar_geometry(&window_for_event);
+            let _ = window_for_event.emit("titlebar-geometry-changed", geometry);
+        }
+    });
+}
+
+/// 窗口在非最大化/非全屏状态下的物理尺寸和位置，即系统点击"还原"按钮后应当回到的矩形
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+struct WindowRect {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+/// 单个窗口需要持久化的布局信息：普通状态下的矩形（如果曾经捕获过），以及最大化/全屏标记
+#[derive(Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+struct WindowState {
+    normal: Option<WindowRect>,
+    maximized: bool,
+    fullscreen: bool,
+}
+
+/// 恢复保存的窗口布局；如果没有保存记录，或保存的窗口位置已经不在当前任何显示器范围内，
+/// 就退回按屏幕宽度计算的默认布局
+fn restore_or_apply_window_state(app: &tauri::AppHandle, window: &tauri::WebviewWindow) {
+    let states = load_window_states(app);
+    if let Some(state) = states.get(window.label()) {
+        match state.normal {
+            Some(normal) if window_rect_fits_available_monitors(window, &normal) => {
+                // 先把窗口摆回普通矩形，这样系统的"还原"操作有正确的 frame 可用，
+                // 再施加最大化/全屏标记，避免把最大化后的尺寸误当成普通尺寸写回
+                let _ = window.set_size(tauri::Size::Physical(tauri::PhysicalSize {
+                    width: normal.width,
+                    height: normal.height,
+                }));
+                let _ = window.set_position(tauri::Position::Physical(tauri::PhysicalPosition {
+                    x: normal.x,
+                    y: normal.y,
+                }));
+                apply_window_maximized_or_fullscreen(window, state);
+                return;
+            }
+            Some(_) => {}
+            None if state.maximized || state.fullscreen => {
+                // 从未在普通状态下保存过矩形（例如一直保持最大化），只应用标记，
+                // 让窗口沿用当前默认尺寸作为还原后的 frame
+                apply_window_maximized_or_fullscreen(window, state);
+                return;
+            }
+            None => {}
+        }
+    }
+
+    apply_window_size_from_screen_width(window, 0.8);
+}
+
+/// 依据保存的状态施加最大化/全屏标记
+fn apply_window_maximized_or_fullscreen(
+    window: &tauri::WebviewWindow,
+    state: &WindowState,
+) {
+    if state.maximized {
+        let _ = window.maximize();
+    }
+    if state.fullscreen {
+        let _ = window.set_fullscreen(true);
+    }
+}
+
+/// 保存的普通矩形的左上角（即窗口位置）是否仍然落在某一块当前可用显示器范围内；
+/// 只检查位置，不要求整个矩形都在该显示器内
+fn window_rect_fits_available_monitors(window: &tauri::WebviewWindow, rect: &WindowRect) -> bool {
+    let Ok(monitors) = window.available_monitors() else {
+        return false;
+    };
+
+    monitors.iter().any(|monitor| {
+        let monitor_pos = monitor.position();
+        let monitor_size = monitor.size();
+        rect.x >= monitor_pos.x
+            && rect.y >= monitor_pos.y
+            && rect.x < monitor_pos.x + monitor_size.width as i32
+            && rect.y < monitor_pos.y + monitor_size.height as i32
+    })
+}
+
+/// 监听窗口移动/缩放（防抖保存）和关闭（立即保存），持久化窗口布局
+fn install_window_state_persistence(app: &tauri::AppHandle, window: &tauri::WebviewWindow) {
+    let app_for_events = app.clone();
+    let window_for_events = window.clone();
+
+    window.on_window_event(move |event| match event {
+        tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+            schedule_window_state_save(&app_for_events, &window_for_events);
+        }
+        tauri::WindowEvent::CloseRequested { .. } => {
+            persist_window_state(&app_for_events, &window_for_events);
+        }
+        tauri::WindowEvent::Destroyed => {
+            cleanup_window_label_caches(window_for_events.label());
+        }
+        _ => {}
+    });
+}
+
+/// 窗口销毁后清理所有按 label 索引的进程内缓存，避免一个 label 被新窗口复用时
+/// 读到上一个窗口留下的脏状态——对交通灯按钮代理尤其重要，它缓存的是指向已被
+/// 系统释放的 `NSView` 的裸指针，复用会是一次悬空指针解引用
+fn cleanup_window_label_caches(window_label: &str) {
+    #[cfg(target_os = "macos")]
+    {
+        if let Ok(mut map) = macos_traffic_lights_button_proxies().lock() {
+            map.remove(window_label);
+        }
+        if let Ok(mut map) = macos_traffic_lights_state().lock() {
+            map.remove(window_label);
+        }
+    }
+    if let Ok(mut map) = window_state_save_schedules().lock() {
+        map.remove(window_label);
+    }
+}
+
+/// 防抖窗口状态保存：移动/缩放期间频繁触发，只在停止变化一段时间后落盘一次。
+/// 每个窗口最多只有一个在跑的等待线程——新事件只是把它的截止时间往后推，不会为每个
+/// `Moved`/`Resized` 事件都新开一个线程。
+fn schedule_window_state_save(app: &tauri::AppHandle, window: &tauri::WebviewWindow) {
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    const DEBOUNCE: Duration = Duration::from_millis(400);
+
+    let label = window.label().to_string();
+    let deadline = Instant::now() + DEBOUNCE;
+
+    let should_spawn_watcher = {
+        let mut map = window_state_save_schedules()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let schedule = map.entry(label.clone()).or_insert(WindowStateSaveSchedule {
+            deadline,
+            watcher_running: false,
+        });
+        schedule.deadline = deadline;
+        if schedule.watcher_running {
+            false
+        } else {
+            schedule.watcher_running = true;
+            true
+        }
+    };
+
+    if !should_spawn_watcher {
+        return;
+    }
+
+    let app_for_thread = app.clone();
+    let window_for_thread = window.clone();
+    thread::spawn(move || {
+        // 只要截止时间还在不断被推后（窗口仍在被拖拽/缩放），就继续等待下一个截止时间
+        loop {
+            let Some(deadline) = window_state_save_schedules()
+                .lock()
+                .ok()
+                .and_then(|map| map.get(&label).map(|schedule| schedule.deadline))
+            else {
+                return;
+            };
+
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            thread::sleep(deadline - now);
+        }
+
+        if let Ok(mut map) = window_state_save_schedules().lock() {
+            if let Some(schedule) = map.get_mut(&label) {
+                schedule.watcher_running = false;
+            }
+        }
+
+        let _ = window_for_thread.clone().run_on_main_thread(move || {
+            persist_window_state(&app_for_thread, &window_for_thread);
+        });
+    });
+}
+
+/// 单个窗口的防抖保存调度：下一次应当落盘的截止时间，以及是否已有等待线程在跑
+#[derive(Clone, Copy)]
+struct WindowStateSaveSchedule {
+    deadline: std::time::Instant,
+    watcher_running: bool,
+}
+
+fn window_state_save_schedules(
+) -> &'static std::sync::Mutex<std::collections::HashMap<String, WindowStateSaveSchedule>> {
+    use std::collections::HashMap;
+    use std::sync::{Mutex, OnceLock};
+
+    static SCHEDULES: OnceLock<Mutex<HashMap<String, WindowStateSaveSchedule>>> = OnceLock::new();
+    SCHEDULES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 读取当前窗口的最大化/全屏状态并立即写入配置目录；如果当前处于最大化/全屏，
+/// 沿用上一次保存的普通矩形，不能把最大化/全屏后的尺寸当成普通矩形写回
+fn persist_window_state(app: &tauri::AppHandle, window: &tauri::WebviewWindow) {
+    let Some(mut state) = capture_window_state(window) else {
+        return;
+    };
+
+    let mut states = load_window_states(app);
+    if state.normal.is_none() {
+        if let Some(previous) = states.get(window.label()) {
+            state.normal = previous.normal;
+        }
+    }
+
+    states.insert(window.label().to_string(), state);
+    save_window_states(app, &states);
+}
+
+fn capture_window_state(window: &tauri::WebviewWindow) -> Option<WindowState> {
+    let maximized = window.is_maximized().unwrap_or(false);
+    let fullscreen = window.is_fullscreen().unwrap_or(false);
+
+    // 最大化/全屏状态下 outer_position/outer_size 反映的是撑满后的 frame，不是用户的
+    // 普通窗口布局，这种情况下不捕获普通矩形，留给调用方沿用上一次保存的值
+    let normal = if maximized || fullscreen {
+        None
+    } else {
+        let position = window.outer_position().ok()?;
+        let size = window.outer_size().ok()?;
+        Some(WindowRect {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        })
+    };
+
+    Some(WindowState {
+        normal,
+        maximized,
+        fullscreen,
+    })
+}
+
+/// 窗口布局存档的路径：`<app_config_dir>/window-state.json`
+fn window_state_file_path(app: &tauri::AppHandle) -> Option<std::path::PathBuf> {
+    use tauri::Manager;
+
+    app.path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join("window-state.json"))
+}
+
+fn load_window_states(app: &tauri::AppHandle) -> std::collections::HashMap<String, WindowState> {
+    let Some(path) = window_state_file_path(app) else {
+        return Default::default();
+    };
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return Default::default();
+    };
+    serde_json::from_str(&contents).unwrap_or_default()
+}
+
+fn save_window_states(
+    app: &tauri::AppHandle,
+    states: &std::collections::HashMap<String, WindowState>,
+) {
+    let Some(path) = window_state_file_path(app) else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string_pretty(states) {
+        let _ = std::fs::write(path, json);
+    }
+}
+
 /// 根据屏幕宽度计算并设置窗口大小和位置
 /// - 参数: window - 要设置的窗口实例
 /// - 参数: width_ratio - 窗口宽度占屏幕宽度的比例
@@ -138,16 +530,13 @@ fn schedule_macos_traffic_lights_initial_adjustment(
     use std::time::Duration;
 
     let window_for_thread = window.clone();
-    let window_label = window.label().to_string();
 
     thread::spawn(move || {
         // 给 titlebar/toolbar 一次布局机会
         thread::sleep(Duration::from_millis(120));
         let window_for_main = window_for_thread.clone();
-        let label_for_main = window_label.clone();
         let _ = window_for_thread.run_on_main_thread(move || {
             apply_macos_traffic_lights_offset(&window_for_main, y_offset, x_offset);
-            set_macos_traffic_lights_offset_dirty_by_label(&label_for_main, false);
         });
     });
 }
@@ -174,9 +563,12 @@ fn install_macos_traffic_lights_live_resize_notifications(
     let center = NSNotificationCenter::defaultCenter();
     let window_label = window.label().to_string();
 
-    set_macos_traffic_lights_offset_dirty_by_label(&window_label, false);
+    set_macos_traffic_lights_offset_by_label(&window_label, y_offset, x_offset);
 
-    let start_label = window_label.clone();
+    // live resize 期间只需要隐藏/恢复按钮透明度；重新定位交给代理容器的
+    // `NSViewFrameDidChangeNotification` 监听器（见 install_macos_traffic_lights_container_pin_observer），
+    // 它在任何标题栏 relayout（包括 live resize）后都会幂等地把容器钉回存储的偏移量，
+    // 不再需要额外的 dirty 标记来判断"要不要重新应用"。
     let start_block = block2::RcBlock::new(move |notification: NonNull<NSNotification>| {
         let notification = unsafe { notification.as_ref() };
         let Some(obj) = notification.object() else {
@@ -186,10 +578,8 @@ fn install_macos_traffic_lights_live_resize_notifications(
             return;
         };
         set_macos_traffic_lights_alpha_nswindow(ns_window, 0.0);
-        set_macos_traffic_lights_offset_dirty_by_label(&start_label, true);
     });
 
-    let end_label = window_label.clone();
     let end_block = block2::RcBlock::new(move |notification: NonNull<NSNotification>| {
         let notification = unsafe { notification.as_ref() };
         let Some(obj) = notification.object() else {
@@ -198,13 +588,42 @@ fn install_macos_traffic_lights_live_resize_notifications(
         let Some(ns_window) = obj.downcast_ref::<NSWindow>() else {
             return;
         };
-        if is_macos_traffic_lights_offset_dirty_by_label(&end_label) {
-            apply_macos_traffic_lights_offset_nswindow(ns_window, y_offset, x_offset);
-            set_macos_traffic_lights_offset_dirty_by_label(&end_label, false);
-        }
         set_macos_traffic_lights_alpha_nswindow(ns_window, 1.0);
     });
 
+    let enter_fullscreen_label = window_label.clone();
+    let enter_fullscreen_block =
+        block2::RcBlock::new(move |notification: NonNull<NSNotification>| {
+            let notification = unsafe { notification.as_ref() };
+            let Some(obj) = notification.object() else {
+                return;
+            };
+            let Some(ns_window) = obj.downcast_ref::<NSWindow>() else {
+                return;
+            };
+            macos_traffic_lights_on_enter_fullscreen(ns_window, &enter_fullscreen_label);
+        });
+
+    let exit_fullscreen_label = window_label.clone();
+    let exit_fullscreen_block =
+        block2::RcBlock::new(move |notification: NonNull<NSNotification>| {
+            let notification = unsafe { notification.as_ref() };
+            let Some(obj) = notification.object() else {
+                return;
+            };
+            let Some(ns_window) = obj.downcast_ref::<NSWindow>() else {
+                return;
+            };
+            let (stored_y, stored_x) =
+                get_macos_traffic_lights_offset_by_label(&exit_fullscreen_label);
+            macos_traffic_lights_on_exit_fullscreen(
+                ns_window,
+                &exit_fullscreen_label,
+                stored_y,
+                stored_x,
+            );
+        });
+
     // SAFETY: 订阅的通知名是有效的，object 过滤为当前 window，block 为可 Send 的 `'static` closure。
     unsafe {
         let start_observer = center.addObserverForName_object_queue_usingBlock(
@@ -223,13 +642,13 @@ fn install_macos_traffic_lights_live_resize_notifications(
             Some(NSWindowDidEnterFullScreenNotification),
             Some(ns_window),
             None,
-            &end_block,
+            &enter_fullscreen_block,
         );
         let fullscreen_exit_observer = center.addObserverForName_object_queue_usingBlock(
             Some(NSWindowDidExitFullScreenNotification),
             Some(ns_window),
             None,
-            &end_block,
+            &exit_fullscreen_block,
         );
 
         // observer token 需要活到窗口结束；这里让其跟随进程生命周期
@@ -267,6 +686,9 @@ fn set_macos_traffic_lights_alpha_nswindow(ns_window: &objc2_app_kit::NSWindow,
 fn apply_macos_traffic_lights_offset(window: &tauri::WebviewWindow, y_offset: f64, x_offset: f64) {
     use objc2_app_kit::NSWindow;
 
+    // 记录本次请求的偏移量，供后续重新布局时复用
+    set_macos_traffic_lights_offset_by_label(window.label(), y_offset, x_offset);
+
     // 获取 NSWindow 实例，失败则返回
     let Ok(ns_window) = window.ns_window() else {
         return;
@@ -274,72 +696,304 @@ fn apply_macos_traffic_lights_offset(window: &tauri::WebviewWindow, y_offset: f6
 
     // 转换为 NSWindow 引用（不安全操作）
     let ns_window = unsafe { &*(ns_window as *const NSWindow) };
-    apply_macos_traffic_lights_offset_nswindow(ns_window, y_offset, x_offset);
+    apply_macos_traffic_lights_offset_nswindow(ns_window, window.label(), y_offset, x_offset);
 }
 
+/// macOS 平台：把代理容器钉到 `(y_offset, x_offset)` 对应的绝对位置
+///
+/// 首次调用时会惰性创建按钮代理（见 [`ensure_macos_traffic_lights_button_proxy`]），
+/// 之后每次都只移动容器本身，是幂等的绝对定位，而不是对按钮 frame 的相对累加。
 #[cfg(target_os = "macos")]
 fn apply_macos_traffic_lights_offset_nswindow(
     ns_window: &objc2_app_kit::NSWindow,
+    window_label: &str,
     y_offset: f64,
     x_offset: f64,
 ) {
-    use objc2::rc::Retained;
-    use objc2_app_kit::{NSButton, NSWindowButton};
-    use objc2_foundation::NSPoint;
+    let Some(proxy) = ensure_macos_traffic_lights_button_proxy(window_label, ns_window) else {
+        return;
+    };
+    reposition_macos_traffic_lights_container(&proxy, y_offset, x_offset);
+}
 
-    /// 移动单个按钮的辅助函数
-    /// - 参数: button - 要移动的按钮实例
-    /// - 参数: y_offset - Y 轴偏移量
-    /// - 参数: x_offset - X 轴偏移量
-    fn move_button(button: &Retained<NSButton>, y_offset: f64, x_offset: f64) {
-        // 获取按钮当前框架
-        let mut frame = button.frame();
-        // 应用偏移量
-        frame.origin.y -= y_offset;
-        frame.origin.x += x_offset;
-        // 设置新位置
-        button.setFrameOrigin(NSPoint {
-            x: frame.origin.x,
-            y: frame.origin.y,
-        });
+/// macOS 平台：惰性创建按钮代理容器——把三个交通灯按钮从标题栏容器挪到我们自己的
+/// `NSView` 里，后续的重新布局只需要移动这个容器，不再触碰按钮本身的 frame。
+/// 做法参考 Electron 的 window-buttons-proxy。
+#[cfg(target_os = "macos")]
+fn ensure_macos_traffic_lights_button_proxy(
+    window_label: &str,
+    ns_window: &objc2_app_kit::NSWindow,
+) -> Option<MacosTrafficLightsButtonProxy> {
+    if let Some(proxy) = macos_traffic_lights_button_proxies()
+        .lock()
+        .ok()
+        .and_then(|map| map.get(window_label).copied())
+    {
+        return Some(proxy);
     }
 
-    // 调整关闭按钮位置
-    if let Some(close) = ns_window.standardWindowButton(NSWindowButton::CloseButton) {
-        move_button(&close, y_offset, x_offset);
+    use objc2_app_kit::{NSView, NSWindowButton};
+    use objc2_foundation::{NSPoint, NSRect, NSSize};
+
+    let close = ns_window.standardWindowButton(NSWindowButton::CloseButton)?;
+    let miniaturize = ns_window.standardWindowButton(NSWindowButton::MiniaturizeButton)?;
+    let zoom = ns_window.standardWindowButton(NSWindowButton::ZoomButton)?;
+
+    // 三个按钮在系统布局下共享同一个 superview，即标题栏容器视图
+    let titlebar_container = close.superview()?;
+
+    // 记录按钮间的固有间距和原始位置，代理容器创建后以此还原系统原生的布局
+    let close_frame = close.frame();
+    let button_spacing = miniaturize.frame().origin.x - close_frame.origin.x;
+    let button_size = close_frame.size;
+
+    let container = unsafe {
+        NSView::initWithFrame(
+            NSView::alloc(),
+            NSRect {
+                origin: close_frame.origin,
+                size: NSSize {
+                    width: button_spacing * 2.0 + button_size.width,
+                    height: button_size.height,
+                },
+            },
+        )
+    };
+
+    unsafe {
+        titlebar_container.addSubview(&container);
+
+        // 把三个按钮从标题栏容器挪进代理容器一次，之后只移动容器本身
+        close.removeFromSuperview();
+        miniaturize.removeFromSuperview();
+        zoom.removeFromSuperview();
+        container.addSubview(&close);
+        container.addSubview(&miniaturize);
+        container.addSubview(&zoom);
     }
-    // 调整最小化按钮位置
-    if let Some(min) = ns_window.standardWindowButton(NSWindowButton::MiniaturizeButton) {
-        move_button(&min, y_offset, x_offset);
+
+    close.setFrameOrigin(NSPoint { x: 0.0, y: 0.0 });
+    miniaturize.setFrameOrigin(NSPoint {
+        x: button_spacing,
+        y: 0.0,
+    });
+    zoom.setFrameOrigin(NSPoint {
+        x: button_spacing * 2.0,
+        y: 0.0,
+    });
+
+    let proxy = MacosTrafficLightsButtonProxy {
+        container_ptr: objc2::rc::Retained::as_ptr(&container) as usize,
+        original_origin: (close_frame.origin.x, close_frame.origin.y),
+    };
+
+    if let Ok(mut map) = macos_traffic_lights_button_proxies().lock() {
+        map.insert(window_label.to_string(), proxy);
     }
-    // 调整最大化按钮位置
-    if let Some(zoom) = ns_window.standardWindowButton(NSWindowButton::ZoomButton) {
-        move_button(&zoom, y_offset, x_offset);
+
+    // 标题栏容器的 frame 在任何 AppKit relayout（主题切换、tab bar、切换 Space）后都可能
+    // 改变，订阅它的变化通知以便代理容器始终被重新钉回存储的偏移量
+    install_macos_traffic_lights_container_pin_observer(window_label, &titlebar_container, proxy);
+
+    Some(proxy)
+}
+
+/// macOS 平台：把交通灯按钮代理容器的 frame 换算成前端可用的标题栏几何信息
+#[cfg(target_os = "macos")]
+fn macos_titlebar_geometry(window: &tauri::WebviewWindow) -> TitlebarGeometry {
+    use objc2_app_kit::{NSView, NSWindow};
+
+    let Ok(ns_window) = window.ns_window() else {
+        return TitlebarGeometry::default();
+    };
+    let ns_window = unsafe { &*(ns_window as *const NSWindow) };
+
+    // 和偏移量应用路径一样惰性创建代理，这样无论调用顺序如何，几何信息都是准确的
+    let Some(proxy) = ensure_macos_traffic_lights_button_proxy(window.label(), ns_window) else {
+        return TitlebarGeometry::default();
+    };
+    let content_height = ns_window.frame().size.height;
+
+    let container = unsafe { &*(proxy.container_ptr as *const NSView) };
+    // 容器的 frame 是相对标题栏视图的坐标，先换算到窗口坐标系（原点在窗口左下角）
+    let frame = unsafe { container.convertRect_toView(container.bounds(), None) };
+
+    TitlebarGeometry {
+        x: 0.0,
+        y: 0.0,
+        // 从窗口左边缘到按钮容器右边缘都应避让，前端只需要一块左上角的保留区域
+        width: frame.origin.x + frame.size.width,
+        // AppKit 的 y 轴原点在左下角，这里换算成前端使用的左上角坐标系
+        height: content_height - frame.origin.y,
     }
 }
 
+/// macOS 平台：把代理容器移动到以创建时原点为基准的绝对坐标，幂等、可重复调用
 #[cfg(target_os = "macos")]
-fn macos_traffic_lights_state() -> &'static std::sync::Mutex<std::collections::HashMap<String, bool>>
-{
+fn reposition_macos_traffic_lights_container(
+    proxy: &MacosTrafficLightsButtonProxy,
+    y_offset: f64,
+    x_offset: f64,
+) {
+    use objc2_app_kit::NSView;
+    use objc2_foundation::NSPoint;
+
+    let container = unsafe { &*(proxy.container_ptr as *const NSView) };
+    let (origin_x, origin_y) = proxy.original_origin;
+    container.setFrameOrigin(NSPoint {
+        x: origin_x + x_offset,
+        y: origin_y - y_offset,
+    });
+}
+
+/// macOS 平台：监听标题栏容器的 frame 变化通知，relayout 后自动重新钉回代理容器
+#[cfg(target_os = "macos")]
+fn install_macos_traffic_lights_container_pin_observer(
+    window_label: &str,
+    titlebar_container: &objc2_app_kit::NSView,
+    proxy: MacosTrafficLightsButtonProxy,
+) {
+    use core::ptr::NonNull;
+    use objc2_app_kit::NSViewFrameDidChangeNotification;
+    use objc2_foundation::{NSNotification, NSNotificationCenter};
+
+    titlebar_container.setPostsFrameChangedNotifications(true);
+
+    let center = NSNotificationCenter::defaultCenter();
+    let label = window_label.to_string();
+    let block = block2::RcBlock::new(move |_notification: NonNull<NSNotification>| {
+        // 全屏期间交由系统管理按钮布局，不要把代理容器钉回去
+        if is_macos_traffic_lights_in_fullscreen_by_label(&label) {
+            return;
+        }
+        let (y_offset, x_offset) = get_macos_traffic_lights_offset_by_label(&label);
+        reposition_macos_traffic_lights_container(&proxy, y_offset, x_offset);
+    });
+
+    // SAFETY: 订阅的通知名有效，object 过滤为标题栏容器视图，block 为可 Send 的 `'static` closure。
+    unsafe {
+        let observer = center.addObserverForName_object_queue_usingBlock(
+            Some(NSViewFrameDidChangeNotification),
+            Some(titlebar_container),
+            None,
+            &block,
+        );
+        // observer token 需要活到窗口结束；这里让其跟随进程生命周期
+        std::mem::forget(observer);
+    }
+}
+
+/// 按钮代理容器的运行时句柄
+#[cfg(target_os = "macos")]
+#[derive(Clone, Copy)]
+struct MacosTrafficLightsButtonProxy {
+    /// 承载三个按钮的容器视图（裸指针；容器已被标题栏容器以 subview 形式持有，生命周期跟随窗口）
+    container_ptr: usize,
+    /// 代理容器创建时的原始 frame 原点，后续的偏移都基于它计算绝对坐标
+    original_origin: (f64, f64),
+}
+
+#[cfg(target_os = "macos")]
+fn macos_traffic_lights_button_proxies(
+) -> &'static std::sync::Mutex<std::collections::HashMap<String, MacosTrafficLightsButtonProxy>> {
     use std::collections::HashMap;
     use std::sync::{Mutex, OnceLock};
 
-    static STATE: OnceLock<Mutex<HashMap<String, bool>>> = OnceLock::new();
+    static PROXIES: OnceLock<Mutex<HashMap<String, MacosTrafficLightsButtonProxy>>> =
+        OnceLock::new();
+    PROXIES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 进入原生全屏时是否恢复系统标题的可见性，对应 Electron 的 `fullscreenWindowTitle` 选项
+#[cfg(target_os = "macos")]
+const MACOS_FULLSCREEN_WINDOW_TITLE_VISIBLE: bool = true;
+
+/// macOS 平台：进入原生全屏——交通灯改由系统在菜单栏显现区域绘制，我们不再重定位它们，
+/// 并按需恢复标题可见性，避免自绘标题栏和系统全屏标题重叠或错位。
+#[cfg(target_os = "macos")]
+fn macos_traffic_lights_on_enter_fullscreen(
+    ns_window: &objc2_app_kit::NSWindow,
+    window_label: &str,
+) {
+    use objc2_app_kit::NSWindowTitleVisibility;
+
+    set_macos_traffic_lights_in_fullscreen_by_label(window_label, true);
+
+    if MACOS_FULLSCREEN_WINDOW_TITLE_VISIBLE {
+        ns_window.setTitleVisibility(NSWindowTitleVisibility::Visible);
+    }
+    set_macos_traffic_lights_alpha_nswindow(ns_window, 1.0);
+}
+
+/// macOS 平台：退出原生全屏——重新隐藏标题、恢复标题栏透明，并把代理容器钉回退出前记录的偏移量
+#[cfg(target_os = "macos")]
+fn macos_traffic_lights_on_exit_fullscreen(
+    ns_window: &objc2_app_kit::NSWindow,
+    window_label: &str,
+    y_offset: f64,
+    x_offset: f64,
+) {
+    use objc2_app_kit::NSWindowTitleVisibility;
+
+    ns_window.setTitleVisibility(NSWindowTitleVisibility::Hidden);
+    ns_window.setTitlebarAppearsTransparent(true);
+    set_macos_traffic_lights_alpha_nswindow(ns_window, 1.0);
+
+    set_macos_traffic_lights_in_fullscreen_by_label(window_label, false);
+    apply_macos_traffic_lights_offset_nswindow(ns_window, window_label, y_offset, x_offset);
+}
+
+/// 交通灯按钮在某个窗口上的运行时状态
+#[cfg(target_os = "macos")]
+#[derive(Clone, Copy, Default)]
+struct MacosTrafficLightsWindowState {
+    /// 当前生效的 (y_offset, x_offset)
+    offset: (f64, f64),
+    /// 窗口当前是否处于原生全屏状态
+    in_fullscreen: bool,
+}
+
+#[cfg(target_os = "macos")]
+fn macos_traffic_lights_state(
+) -> &'static std::sync::Mutex<std::collections::HashMap<String, MacosTrafficLightsWindowState>> {
+    use std::collections::HashMap;
+    use std::sync::{Mutex, OnceLock};
+
+    static STATE: OnceLock<Mutex<HashMap<String, MacosTrafficLightsWindowState>>> =
+        OnceLock::new();
     STATE.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
 #[cfg(target_os = "macos")]
-fn is_macos_traffic_lights_offset_dirty_by_label(window_label: &str) -> bool {
+fn get_macos_traffic_lights_offset_by_label(window_label: &str) -> (f64, f64) {
     macos_traffic_lights_state()
         .lock()
         .ok()
-        .and_then(|map| map.get(window_label).copied())
+        .and_then(|map| map.get(window_label).map(|state| state.offset))
+        .unwrap_or((0.0, 0.0))
+}
+
+#[cfg(target_os = "macos")]
+fn set_macos_traffic_lights_offset_by_label(window_label: &str, y_offset: f64, x_offset: f64) {
+    if let Ok(mut map) = macos_traffic_lights_state().lock() {
+        map.entry(window_label.to_string()).or_default().offset = (y_offset, x_offset);
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn is_macos_traffic_lights_in_fullscreen_by_label(window_label: &str) -> bool {
+    macos_traffic_lights_state()
+        .lock()
+        .ok()
+        .and_then(|map| map.get(window_label).map(|state| state.in_fullscreen))
         .unwrap_or(false)
 }
 
 #[cfg(target_os = "macos")]
-fn set_macos_traffic_lights_offset_dirty_by_label(window_label: &str, dirty: bool) {
+fn set_macos_traffic_lights_in_fullscreen_by_label(window_label: &str, in_fullscreen: bool) {
     if let Ok(mut map) = macos_traffic_lights_state().lock() {
-        map.insert(window_label.to_string(), dirty);
+        map.entry(window_label.to_string())
+            .or_default()
+            .in_fullscreen = in_fullscreen;
     }
 }